@@ -0,0 +1,19 @@
+#[macro_use]
+extern crate failure;
+
+#[macro_use]
+extern crate tcx_chain;
+
+pub mod address;
+pub mod psbt;
+pub mod transaction;
+
+use core::result;
+
+pub type Result<T> = result::Result<T, failure::Error>;
+
+#[derive(Fail, Debug, PartialEq)]
+pub enum Error {
+    #[fail(display = "unsupported_chain")]
+    UnsupportedChain,
+}
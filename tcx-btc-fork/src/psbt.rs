@@ -0,0 +1,435 @@
+use crate::address::BtcForkAddress;
+use crate::transaction::ScriptPubKeyComponent;
+use crate::Result;
+
+use bitcoin::blockdata::script::{Builder, Script};
+use bitcoin::consensus::encode::{serialize, Decodable, Encodable, VarInt};
+use bitcoin::util::address::Payload;
+use bitcoin::util::bip143::SighashComponents;
+use bitcoin::{Transaction, TxOut};
+use bitcoin_hashes::hash160;
+use bitcoin_hashes::Hash;
+
+use std::io::Cursor;
+use std::str::FromStr;
+
+use tcx_chain::{HdKeystore, Secp256k1PublicKey};
+use tcx_primitive::{Pair, Secp256k1Pair};
+
+use failure::format_err;
+
+/// BIP-174 magic bytes: `psbt` followed by the `0xff` separator.
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+const PSBT_GLOBAL_UNSIGNED_TX: u8 = 0x00;
+
+const PSBT_IN_WITNESS_UTXO: u8 = 0x01;
+const PSBT_IN_PARTIAL_SIG: u8 = 0x02;
+const PSBT_IN_REDEEM_SCRIPT: u8 = 0x04;
+const PSBT_IN_WITNESS_SCRIPT: u8 = 0x05;
+const PSBT_IN_BIP32_DERIVATION: u8 = 0x06;
+const PSBT_IN_FINAL_SCRIPTSIG: u8 = 0x07;
+const PSBT_IN_FINAL_SCRIPTWITNESS: u8 = 0x08;
+
+/// Per-input PSBT metadata collected while signers do their work. Only the
+/// fields the btc-fork module populates and consumes are modelled here.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct PsbtInput {
+    /// The output being spent, used for witness sighash computation.
+    pub witness_utxo: Option<TxOut>,
+    /// The redeem script for the P2SH-P2WPKH payloads this crate builds.
+    pub redeem_script: Option<Script>,
+    /// The witness script for native-segwit payloads.
+    pub witness_script: Option<Script>,
+    /// BIP-32 derivation hint: `(pubkey, derivation_path)`.
+    pub bip32_derivation: Option<(Vec<u8>, String)>,
+    /// Collected partial signatures keyed by the signing public key.
+    pub partial_sigs: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Finalized input scripts, filled in by [`Psbt::finalize`].
+    pub final_script_sig: Option<Script>,
+    pub final_script_witness: Option<Vec<Vec<u8>>>,
+}
+
+/// A partially-signed bitcoin transaction for the btc-fork chains, following
+/// the BIP-174 key/value map layout. Unlike [`TraitTransactionSigner`], a
+/// `Psbt` can be handed between signers or to a watch-only flow before it is
+/// finalized into a broadcastable transaction.
+pub struct Psbt {
+    pub unsigned_tx: Transaction,
+    pub inputs: Vec<PsbtInput>,
+}
+
+impl Psbt {
+    /// Build a PSBT from an unsigned transaction and its prevouts, each given
+    /// as `(prev_address, value, derivation_path)`. For every input this
+    /// derives the spending public key from `keystore` at `derivation_path`
+    /// and fills in the `witness_utxo` (via [`ScriptPubKeyComponent`]), the
+    /// BIP-32 derivation hint `sign_psbt` keys ownership off of, and — for the
+    /// nested-segwit (P2SH-P2WPKH) payloads this crate builds — the
+    /// `redeem_script` needed to finalize the spend.
+    pub fn new(
+        keystore: &HdKeystore,
+        password: &str,
+        unsigned_tx: Transaction,
+        prevouts: &[(&str, u64, &str)],
+    ) -> Result<Psbt> {
+        tcx_ensure!(
+            unsigned_tx.input.len() == prevouts.len(),
+            format_err!("prevout_count_mismatch")
+        );
+        let mut inputs = Vec::with_capacity(prevouts.len());
+        for (addr, value, path) in prevouts {
+            let script_pubkey = BtcForkAddress::address_script_pub_key(addr)?;
+            let pair = keystore.get_pair::<Secp256k1Pair>(path, password)?;
+            let pub_key = pair.public_key().to_bytes();
+            let hash160 = hash160::Hash::hash(&pub_key);
+
+            // nested segwit spends push the `0x0014<hash160>` witness program
+            // as the P2SH redeem script; native segwit needs neither. Legacy
+            // P2PKH prevouts would require the pre-segwit sighash and a
+            // scriptSig, which this path does not build — reject them rather
+            // than emit a malformed spend.
+            let redeem_script = match BtcForkAddress::from_str(addr)?.payload {
+                Payload::ScriptHash(_) => Some(
+                    Builder::new()
+                        .push_int(0)
+                        .push_slice(&hash160[..])
+                        .into_script(),
+                ),
+                Payload::WitnessProgram { .. } => None,
+                Payload::PubkeyHash(_) => {
+                    return Err(format_err!("legacy_p2pkh_input_not_supported"));
+                }
+            };
+
+            inputs.push(PsbtInput {
+                witness_utxo: Some(TxOut {
+                    value: *value,
+                    script_pubkey,
+                }),
+                redeem_script,
+                bip32_derivation: Some((pub_key, path.to_string())),
+                ..Default::default()
+            });
+        }
+        Ok(Psbt {
+            unsigned_tx,
+            inputs,
+        })
+    }
+
+    /// Serialize to the BIP-174 binary format: the magic, the global map (just
+    /// the unsigned transaction), then one map per input and per output, each
+    /// terminated by a `0x00` separator.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(PSBT_MAGIC);
+
+        // global map
+        write_keypair(&mut buf, &[PSBT_GLOBAL_UNSIGNED_TX], &serialize(&self.unsigned_tx));
+        buf.push(0x00);
+
+        // input maps
+        for input in &self.inputs {
+            if let Some(utxo) = &input.witness_utxo {
+                write_keypair(&mut buf, &[PSBT_IN_WITNESS_UTXO], &serialize(utxo));
+            }
+            for (pubkey, sig) in &input.partial_sigs {
+                let mut key = vec![PSBT_IN_PARTIAL_SIG];
+                key.extend_from_slice(pubkey);
+                write_keypair(&mut buf, &key, sig);
+            }
+            if let Some(redeem) = &input.redeem_script {
+                write_keypair(&mut buf, &[PSBT_IN_REDEEM_SCRIPT], redeem.as_bytes());
+            }
+            if let Some(witness) = &input.witness_script {
+                write_keypair(&mut buf, &[PSBT_IN_WITNESS_SCRIPT], witness.as_bytes());
+            }
+            if let Some((pubkey, path)) = &input.bip32_derivation {
+                let mut key = vec![PSBT_IN_BIP32_DERIVATION];
+                key.extend_from_slice(pubkey);
+                write_keypair(&mut buf, &key, path.as_bytes());
+            }
+            if let Some(script_sig) = &input.final_script_sig {
+                write_keypair(&mut buf, &[PSBT_IN_FINAL_SCRIPTSIG], script_sig.as_bytes());
+            }
+            if let Some(witness) = &input.final_script_witness {
+                write_keypair(&mut buf, &[PSBT_IN_FINAL_SCRIPTWITNESS], &serialize_witness(witness));
+            }
+            buf.push(0x00);
+        }
+
+        // output maps (empty for the payloads this crate builds)
+        for _ in &self.unsigned_tx.output {
+            buf.push(0x00);
+        }
+        buf
+    }
+
+    /// Assemble the `script_sig`/witness for each input from the collected
+    /// partial signatures, turning the PSBT into a broadcastable transaction.
+    pub fn finalize(&mut self) -> Result<Transaction> {
+        let mut tx = self.unsigned_tx.clone();
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            let (pubkey, sig) = input
+                .partial_sigs
+                .first()
+                .cloned()
+                .ok_or_else(|| format_err!("missing_partial_sig"))?;
+
+            if let Some(redeem) = &input.redeem_script {
+                // P2SH-P2WPKH: script_sig is the redeem script, witness carries the sig.
+                tx.input[i].script_sig = Builder::new().push_slice(redeem.as_bytes()).into_script();
+                tx.input[i].witness = vec![sig, pubkey];
+            } else {
+                // native P2WPKH
+                tx.input[i].witness = vec![sig, pubkey];
+            }
+            input.final_script_sig = Some(tx.input[i].script_sig.clone());
+            input.final_script_witness = Some(tx.input[i].witness.clone());
+        }
+        Ok(tx)
+    }
+}
+
+/// Sign a PSBT with the inputs a keystore owns, without finalizing.
+pub trait PsbtSigner {
+    fn sign_psbt(&self, psbt: &mut Psbt, password: Option<&str>) -> Result<()>;
+}
+
+impl PsbtSigner for HdKeystore {
+    fn sign_psbt(&self, psbt: &mut Psbt, password: Option<&str>) -> Result<()> {
+        tcx_ensure!(password.is_some(), tcx_crypto::Error::InvalidPassword);
+        let password = password.unwrap();
+
+        let tx = psbt.unsigned_tx.clone();
+        for (i, input) in psbt.inputs.iter_mut().enumerate() {
+            let (_, path) = match &input.bip32_derivation {
+                Some(d) => d.clone(),
+                None => continue,
+            };
+            let utxo = input
+                .witness_utxo
+                .as_ref()
+                .ok_or_else(|| format_err!("missing_witness_utxo"))?;
+            // only segwit prevouts use the BIP-143 sighash computed below
+            tcx_ensure!(
+                utxo.script_pubkey.is_v0_p2wpkh() || utxo.script_pubkey.is_p2sh(),
+                format_err!("legacy_p2pkh_input_not_supported")
+            );
+
+            let pair = self.get_pair::<Secp256k1Pair>(&path, password)?;
+            let pub_key = Secp256k1PublicKey::from_slice(&pair.public_key().to_bytes())?;
+            let hash160 = hash160::Hash::hash(&pair.public_key().to_bytes());
+
+            // P2SH-P2WPKH / P2WPKH both sign against the implied P2PKH script.
+            let script_code = Builder::new()
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_DUP)
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_HASH160)
+                .push_slice(&hash160[..])
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_EQUALVERIFY)
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_CHECKSIG)
+                .into_script();
+
+            let components = SighashComponents::new(&tx);
+            let sighash = components.sighash_all(&tx.input[i], &script_code, utxo.value);
+
+            let mut sig = pair.sign(&sighash[..])?;
+            sig.push(0x01); // SIGHASH_ALL
+            input
+                .partial_sigs
+                .push((pub_key.to_bytes(), sig));
+        }
+        Ok(())
+    }
+}
+
+fn write_keypair(buf: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    write_compact(buf, key.len() as u64);
+    buf.extend_from_slice(key);
+    write_compact(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn write_compact(buf: &mut Vec<u8>, n: u64) {
+    VarInt(n).consensus_encode(buf).expect("varint");
+}
+
+fn serialize_witness(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_compact(&mut buf, items.len() as u64);
+    for item in items {
+        write_compact(&mut buf, item.len() as u64);
+        buf.extend_from_slice(item);
+    }
+    buf
+}
+
+fn deserialize_witness(bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut cursor = Cursor::new(bytes);
+    let count = VarInt::consensus_decode(&mut cursor)?.0 as usize;
+    let mut items = Vec::with_capacity(count);
+    for _ in 0..count {
+        items.push(read_field(&mut cursor)?);
+    }
+    Ok(items)
+}
+
+/// Parse a PSBT produced by [`Psbt::serialize`] back into its unsigned
+/// transaction and per-input maps.
+pub fn deserialize(bytes: &[u8]) -> Result<Psbt> {
+    tcx_ensure!(bytes.starts_with(PSBT_MAGIC), format_err!("invalid_psbt_magic"));
+    let mut cursor = Cursor::new(&bytes[PSBT_MAGIC.len()..]);
+
+    // global map: read until separator, keeping the unsigned tx
+    let mut unsigned_tx = None;
+    loop {
+        let key = read_field(&mut cursor)?;
+        if key.is_empty() {
+            break;
+        }
+        let value = read_field(&mut cursor)?;
+        if key[0] == PSBT_GLOBAL_UNSIGNED_TX {
+            unsigned_tx = Some(Transaction::consensus_decode(&mut Cursor::new(value))?);
+        }
+    }
+    let unsigned_tx = unsigned_tx.ok_or_else(|| format_err!("missing_unsigned_tx"))?;
+
+    let mut inputs = Vec::with_capacity(unsigned_tx.input.len());
+    for _ in 0..unsigned_tx.input.len() {
+        let mut input = PsbtInput::default();
+        loop {
+            let key = read_field(&mut cursor)?;
+            if key.is_empty() {
+                break;
+            }
+            let value = read_field(&mut cursor)?;
+            match key[0] {
+                PSBT_IN_WITNESS_UTXO => {
+                    input.witness_utxo = Some(TxOut::consensus_decode(&mut Cursor::new(value))?);
+                }
+                PSBT_IN_PARTIAL_SIG => input.partial_sigs.push((key[1..].to_vec(), value)),
+                PSBT_IN_REDEEM_SCRIPT => input.redeem_script = Some(Script::from(value)),
+                PSBT_IN_WITNESS_SCRIPT => input.witness_script = Some(Script::from(value)),
+                PSBT_IN_BIP32_DERIVATION => {
+                    input.bip32_derivation =
+                        Some((key[1..].to_vec(), String::from_utf8_lossy(&value).to_string()));
+                }
+                PSBT_IN_FINAL_SCRIPTSIG => input.final_script_sig = Some(Script::from(value)),
+                PSBT_IN_FINAL_SCRIPTWITNESS => {
+                    input.final_script_witness = Some(deserialize_witness(&value)?)
+                }
+                _ => {}
+            }
+        }
+        inputs.push(input);
+    }
+
+    Ok(Psbt {
+        unsigned_tx,
+        inputs,
+    })
+}
+
+fn read_field(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>> {
+    let len = VarInt::consensus_decode(cursor)?.0 as usize;
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let mut buf = vec![0u8; len];
+    std::io::Read::read_exact(cursor, &mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::BtcForkAddress;
+
+    use bitcoin::{OutPoint, TxIn};
+
+    use tcx_chain::keystore::EmptyExtra;
+    use tcx_chain::{CoinInfo, Metadata};
+    use tcx_primitive::CurveType;
+
+    static PASSWORD: &'static str = "Insecure Pa55w0rd";
+    static MNEMONIC: &'static str =
+        "inject kidney empty canal shadow pact comfort wife crush horse wife sketch";
+
+    fn keystore_with_btc_account() -> (HdKeystore, String, String) {
+        let mut keystore = HdKeystore::from_mnemonic(&MNEMONIC, &PASSWORD, Metadata::default());
+        let coin_info = CoinInfo {
+            symbol: "BTC".to_string(),
+            derivation_path: "m/49'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+        };
+        let account = keystore
+            .derive_coin::<BtcForkAddress, EmptyExtra>(&coin_info, &PASSWORD)
+            .unwrap();
+        (
+            keystore,
+            account.address.clone(),
+            account.derivation_path.clone(),
+        )
+    }
+
+    fn unsigned_tx(address: &str) -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Script::new(),
+                sequence: 0xffff_ffff,
+                witness: vec![],
+            }],
+            output: vec![TxOut {
+                value: 90_000,
+                script_pubkey: BtcForkAddress::address_script_pub_key(address).unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_build_sign_finalize() {
+        let (keystore, address, path) = keystore_with_btc_account();
+        let tx = unsigned_tx(&address);
+
+        let mut psbt = Psbt::new(&keystore, PASSWORD, tx, &[(&address, 100_000, &path)]).unwrap();
+        // the derivation hint must be populated or sign_psbt skips the input
+        assert!(psbt.inputs[0].bip32_derivation.is_some());
+        assert!(psbt.inputs[0].redeem_script.is_some());
+
+        keystore.sign_psbt(&mut psbt, Some(PASSWORD)).unwrap();
+        assert_eq!(psbt.inputs[0].partial_sigs.len(), 1);
+
+        let finalized = psbt.finalize().unwrap();
+        // nested segwit: non-empty script_sig plus a two-item witness
+        assert!(!finalized.input[0].script_sig.is_empty());
+        assert_eq!(finalized.input[0].witness.len(), 2);
+    }
+
+    #[test]
+    fn test_serialize_deserialize_round_trip() {
+        let (keystore, address, path) = keystore_with_btc_account();
+        let tx = unsigned_tx(&address);
+
+        let mut psbt = Psbt::new(&keystore, PASSWORD, tx, &[(&address, 100_000, &path)]).unwrap();
+        keystore.sign_psbt(&mut psbt, Some(PASSWORD)).unwrap();
+
+        let bytes = psbt.serialize();
+        let parsed = deserialize(&bytes).unwrap();
+
+        assert_eq!(parsed.unsigned_tx.txid(), psbt.unsigned_tx.txid());
+        assert_eq!(parsed.inputs.len(), 1);
+        assert_eq!(parsed.inputs[0].partial_sigs, psbt.inputs[0].partial_sigs);
+
+        // a finalized PSBT survives the round trip too
+        let final_tx = psbt.finalize().unwrap();
+        let reparsed = deserialize(&psbt.serialize()).unwrap();
+        assert_eq!(
+            reparsed.inputs[0].final_script_witness.as_ref().unwrap().len(),
+            final_tx.input[0].witness.len()
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use crate::Result;
+use bitcoin::Script;
+
+/// Builds the `script_pubkey` for an address, letting the signing and PSBT
+/// paths recover a previous output's locking script from its address.
+pub trait ScriptPubKeyComponent {
+    /// The `script_pubkey` for an address derived "like" `target_addr` from
+    /// `pub_key` (same payload kind, same network).
+    fn address_like(target_addr: &str, pub_key: &bitcoin::PublicKey) -> Result<Script>;
+
+    /// The `script_pubkey` encoded by `target_addr` itself.
+    fn address_script_pub_key(target_addr: &str) -> Result<Script>;
+}